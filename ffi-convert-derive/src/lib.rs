@@ -0,0 +1,185 @@
+//! Derive macros for the `CReprOf`, `AsRust` and `CDrop` traits of the
+//! `ffi-convert` crate.
+//!
+//! The struct a derive is applied to is the C representation; the Rust type it
+//! converts to/from is given by the mandatory `#[target_type(...)]` attribute.
+//!
+//! Individual fields whose type does not fit the standard
+//! `CReprOf`/`AsRust`/`CDrop` scheme (enums encoded as integers, hand-written C
+//! types, bit-packed flags, ...) can opt into a bespoke conversion with
+//! `#[ffi_convert(with = "path::to::module")]` (`convert_with` is accepted as an
+//! alias), pointing at a module exposing `c_repr_of`, `as_rust` and `do_drop`
+//! free functions. All three are required: `do_drop` is always called for the
+//! field and has no default.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, LitStr, Path};
+
+/// The per-field `#[ffi_convert(with = "...")]` override, if present.
+struct FieldOverride {
+    module: Path,
+}
+
+fn parse_field_override(field: &Field) -> syn::Result<Option<FieldOverride>> {
+    for attr in &field.attrs {
+        if !attr.path().is_ident("ffi_convert") {
+            continue;
+        }
+        let mut module = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("with") || meta.path.is_ident("convert_with") {
+                let lit: LitStr = meta.value()?.parse()?;
+                module = Some(lit.parse::<Path>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown `ffi_convert` attribute, expected `with`"))
+            }
+        })?;
+        if let Some(module) = module {
+            return Ok(Some(FieldOverride { module }));
+        }
+    }
+    Ok(None)
+}
+
+fn target_type(input: &DeriveInput) -> syn::Result<Path> {
+    let attr = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("target_type"))
+        .ok_or_else(|| {
+            syn::Error::new_spanned(&input.ident, "missing `#[target_type(...)]` attribute")
+        })?;
+    attr.parse_args::<Path>()
+}
+
+fn named_fields(input: &DeriveInput) -> syn::Result<&Fields> {
+    match &input.data {
+        Data::Struct(data) if !matches!(data.fields, Fields::Unnamed(_)) => Ok(&data.fields),
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            "this derive only supports structs with named fields",
+        )),
+    }
+}
+
+#[proc_macro_derive(CReprOf, attributes(target_type, ffi_convert))]
+pub fn derive_c_repr_of(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+    expand_c_repr_of(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_c_repr_of(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let c_type = &input.ident;
+    let rust_type = target_type(input)?;
+
+    let mut fields = Vec::new();
+    for field in named_fields(input)? {
+        let name = field.ident.as_ref().expect("named field");
+        fields.push(match parse_field_override(field)? {
+            Some(FieldOverride { module }) => quote! {
+                #name: #module::c_repr_of(input.#name)?
+            },
+            None => quote! {
+                #name: ffi_convert::CReprOf::c_repr_of(input.#name)?
+            },
+        });
+    }
+
+    Ok(quote! {
+        impl ffi_convert::CReprOf<#rust_type> for #c_type {
+            fn c_repr_of(input: #rust_type) -> ::std::result::Result<Self, ::failure::Error> {
+                use ::failure::ResultExt;
+                Ok(Self {
+                    #(#fields),*
+                })
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(AsRust, attributes(target_type, ffi_convert))]
+pub fn derive_as_rust(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+    expand_as_rust(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_as_rust(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let c_type = &input.ident;
+    let rust_type = target_type(input)?;
+
+    let mut fields = Vec::new();
+    for field in named_fields(input)? {
+        let name = field.ident.as_ref().expect("named field");
+        fields.push(match parse_field_override(field)? {
+            Some(FieldOverride { module }) => quote! {
+                #name: #module::as_rust(&self.#name)?
+            },
+            None => quote! {
+                #name: ffi_convert::AsRust::as_rust(&self.#name)?
+            },
+        });
+    }
+
+    Ok(quote! {
+        impl ffi_convert::AsRust<#rust_type> for #c_type {
+            fn as_rust(&self) -> ::std::result::Result<#rust_type, ::failure::Error> {
+                use ::failure::ResultExt;
+                Ok(#rust_type {
+                    #(#fields),*
+                })
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(CDrop, attributes(target_type, ffi_convert))]
+pub fn derive_c_drop(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+    expand_c_drop(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_c_drop(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let c_type = &input.ident;
+
+    let mut fields = Vec::new();
+    for field in named_fields(input)? {
+        let name = field.ident.as_ref().expect("named field");
+        fields.push(match parse_field_override(field)? {
+            Some(FieldOverride { module }) => quote! {
+                #module::do_drop(&mut self.#name)?;
+            },
+            None => quote! {
+                ffi_convert::CDrop::do_drop(&mut self.#name)?;
+            },
+        });
+    }
+
+    // Emit the matching `Drop` too: the crate model is "Drop calls do_drop", and
+    // owning utility types (`CArray`, `CFixedArray`, ...) rely on derived structs
+    // freeing their nested C allocations when dropped.
+    Ok(quote! {
+        impl ffi_convert::CDrop for #c_type {
+            fn do_drop(&mut self) -> ::std::result::Result<(), ::failure::Error> {
+                #(#fields)*
+                Ok(())
+            }
+        }
+
+        impl ::std::ops::Drop for #c_type {
+            fn drop(&mut self) {
+                let _ = ffi_convert::CDrop::do_drop(self);
+            }
+        }
+    })
+}
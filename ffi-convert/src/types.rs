@@ -1,7 +1,9 @@
 //! This module contains definitions of utility types that implement the [`CReprOf`], [`AsRust`], and [`CDrop`] traits.
 //!
 
-use std::ffi::CString;
+use std::convert::TryInto;
+use std::ffi::{CString, OsString};
+use std::path::PathBuf;
 use std::ptr::null;
 use std::ops::Range;
 
@@ -105,6 +107,55 @@ impl CDrop for CStringArray {
 /// let ctoppings = CArray::<CPizzaTopping>::c_repr_of(toppings);
 ///
 /// ```
+///
+/// When an element conversion fails partway through, the error is propagated and
+/// the elements already converted are freed exactly once before returning:
+///
+/// ```
+/// use ffi_convert::{CReprOf, AsRust, CDrop, CArray};
+/// use failure::{bail, Error};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static FREED: AtomicUsize = AtomicUsize::new(0);
+///
+/// struct CFallible {
+///     ok: bool,
+/// }
+///
+/// impl CReprOf<bool> for CFallible {
+///     fn c_repr_of(input: bool) -> Result<Self, Error> {
+///         if input {
+///             Ok(CFallible { ok: true })
+///         } else {
+///             bail!("conversion deliberately failed")
+///         }
+///     }
+/// }
+///
+/// impl AsRust<bool> for CFallible {
+///     fn as_rust(&self) -> Result<bool, Error> {
+///         Ok(self.ok)
+///     }
+/// }
+///
+/// impl CDrop for CFallible {
+///     fn do_drop(&mut self) -> Result<(), Error> {
+///         FREED.fetch_add(1, Ordering::SeqCst);
+///         Ok(())
+///     }
+/// }
+///
+/// impl Drop for CFallible {
+///     fn drop(&mut self) {
+///         let _ = self.do_drop();
+///     }
+/// }
+///
+/// let result = CArray::<CFallible>::c_repr_of(vec![true, true, false]);
+/// assert!(result.is_err());
+/// // The two elements converted before the failure were each freed exactly once.
+/// assert_eq!(FREED.load(Ordering::SeqCst), 2);
+/// ```
 #[repr(C)]
 #[derive(Debug)]
 pub struct CArray<T> {
@@ -129,19 +180,30 @@ impl<U: AsRust<V>, V> AsRust<Vec<V>> for CArray<U> {
 impl<U: CReprOf<V> + CDrop, V> CReprOf<Vec<V>> for CArray<U> {
     fn c_repr_of(input: Vec<V>) -> Result<Self, Error> {
         let input_size = input.len();
+        let data_ptr = if input_size > 0 {
+            let mut converted: Vec<U> = Vec::with_capacity(input_size);
+            for item in input {
+                match U::c_repr_of(item) {
+                    Ok(c_item) => converted.push(c_item),
+                    Err(e) => {
+                        // Free what we already converted so no C strings or
+                        // nested allocations leak before we bail out. The crate
+                        // model is "Drop calls do_drop", so `mem::forget` after
+                        // the manual `do_drop` to avoid freeing twice.
+                        for mut already in converted {
+                            let _ = already.do_drop();
+                            std::mem::forget(already);
+                        }
+                        return Err(e).context("Could not convert Vector to C Repr")?;
+                    }
+                }
+            }
+            Box::into_raw(converted.into_boxed_slice()) as *const U
+        } else {
+            null() as *const U
+        };
         Ok(Self {
-            data_ptr: if input_size > 0 {
-                Box::into_raw(
-                    input
-                        .into_iter()
-                        .map(|item| U::c_repr_of(item))
-                        .collect::<Result<Vec<_>, Error>>()
-                        .expect("Could not convert to C representation")
-                        .into_boxed_slice(),
-                ) as *const U
-            } else {
-                null() as *const U
-            },
+            data_ptr,
             size: input_size,
         })
     }
@@ -165,6 +227,429 @@ impl<T> Drop for CArray<T> {
     }
 }
 
+/// A utility type to represent a contiguous byte buffer.
+///
+/// Unlike `CArray<u8>`, which converts its elements one by one through their
+/// `CReprOf`/`AsRust` implementations, `CByteArray` moves the whole buffer
+/// across the boundary at once (`Box<[u8]>` into and out of raw) with no
+/// per-byte conversion or allocation, matching how binary payloads are usually
+/// exchanged with C/C++.
+///
+/// # Example
+///
+/// ```
+/// use ffi_convert::{CReprOf, AsRust, CByteArray};
+///
+/// let blob: Vec<u8> = vec![0xDE, 0xAD, 0xBE, 0xEF];
+/// let c_blob = CByteArray::c_repr_of(blob.clone()).expect("could not convert !");
+/// assert_eq!(blob, c_blob.as_rust().expect("could not convert !"));
+/// ```
+#[repr(C)]
+#[derive(Debug)]
+pub struct CByteArray {
+    /// Pointer to the first byte of the buffer
+    pub data: *const u8,
+    /// Number of bytes in the buffer
+    // Note: we can't use `libc::size_t` because it's not supported by JNA
+    pub size: libc::c_int,
+}
+
+unsafe impl Sync for CByteArray {}
+
+impl CReprOf<Vec<u8>> for CByteArray {
+    fn c_repr_of(input: Vec<u8>) -> Result<Self, Error> {
+        let boxed = input.into_boxed_slice();
+        Ok(Self {
+            size: boxed.len() as libc::c_int,
+            data: Box::into_raw(boxed) as *const u8,
+        })
+    }
+}
+
+impl AsRust<Vec<u8>> for CByteArray {
+    fn as_rust(&self) -> Result<Vec<u8>, Error> {
+        Ok(if self.size <= 0 {
+            vec![]
+        } else {
+            unsafe { std::slice::from_raw_parts(self.data, self.size as usize) }.to_vec()
+        })
+    }
+}
+
+impl CReprOf<Box<[u8]>> for CByteArray {
+    fn c_repr_of(input: Box<[u8]>) -> Result<Self, Error> {
+        Ok(Self {
+            size: input.len() as libc::c_int,
+            data: Box::into_raw(input) as *const u8,
+        })
+    }
+}
+
+impl AsRust<Box<[u8]>> for CByteArray {
+    fn as_rust(&self) -> Result<Box<[u8]>, Error> {
+        let vec: Vec<u8> = self.as_rust()?;
+        Ok(vec.into_boxed_slice())
+    }
+}
+
+impl CDrop for CByteArray {
+    fn do_drop(&mut self) -> Result<(), Error> {
+        if !self.data.is_null() {
+            let _ = unsafe {
+                Box::from_raw(std::slice::from_raw_parts_mut(
+                    self.data as *mut u8,
+                    self.size as usize,
+                ))
+            };
+        }
+        Ok(())
+    }
+}
+
+impl Drop for CByteArray {
+    fn drop(&mut self) {
+        let _ = self.do_drop();
+    }
+}
+
+/// A borrowed, non-owning view over a C string lent by foreign code.
+///
+/// Unlike [`CStringArray`] and [`CArray`], which own their backing allocation
+/// and free it in `do_drop`, a `CStrView` only *reads* memory owned by the C
+/// side. Its [`CDrop`] is a no-op and it never calls `Box::from_raw`, so it can
+/// safely wrap a pointer whose lifetime is managed elsewhere without risking a
+/// double free.
+///
+/// # Example
+///
+/// ```no_run
+/// use ffi_convert::{AsRust, CStrView};
+/// use libc::c_char;
+///
+/// # let lent: *const c_char = std::ptr::null();
+/// let view = CStrView { data: lent };
+/// let owned = view.as_rust().expect("could not convert !");
+/// ```
+#[repr(C)]
+#[derive(Debug)]
+pub struct CStrView {
+    /// Pointer to a nul-terminated string owned by foreign code.
+    pub data: *const libc::c_char,
+}
+
+unsafe impl Sync for CStrView {}
+
+impl AsRust<String> for CStrView {
+    fn as_rust(&self) -> Result<String, Error> {
+        Ok(create_rust_string_from!(self.data))
+    }
+}
+
+impl CDrop for CStrView {
+    fn do_drop(&mut self) -> Result<(), Error> {
+        // Borrowed view: the backing memory is owned by the C side, so there is
+        // nothing for us to free here.
+        Ok(())
+    }
+}
+
+/// A borrowed, non-owning view over an array lent by foreign code.
+///
+/// This is the borrowed counterpart of [`CArray`]: it reads `size` elements of
+/// the parametrized type through their own [`AsRust`] implementation but, like
+/// [`CStrView`], never owns the allocation. Its [`CDrop`] is a no-op and it
+/// never calls `Box::from_raw`.
+///
+/// # Example
+///
+/// ```no_run
+/// use ffi_convert::{AsRust, CArrayView};
+///
+/// # let data_ptr: *const u8 = std::ptr::null();
+/// let view = CArrayView::<u8> { data_ptr, size: 0 };
+/// let owned: Vec<u8> = view.as_rust().expect("could not convert !");
+/// ```
+#[repr(C)]
+#[derive(Debug)]
+pub struct CArrayView<T> {
+    /// Pointer to the first element of an array owned by foreign code.
+    pub data_ptr: *const T,
+    /// Number of elements pointed to by `data_ptr`.
+    pub size: usize,
+}
+
+impl<U: AsRust<V>, V> AsRust<Vec<V>> for CArrayView<U> {
+    fn as_rust(&self) -> Result<Vec<V>, Error> {
+        let mut vec = Vec::with_capacity(self.size);
+        if self.size > 0 {
+            let values =
+                unsafe { std::slice::from_raw_parts(self.data_ptr, self.size) };
+            for value in values {
+                vec.push(value.as_rust()?);
+            }
+        }
+        Ok(vec)
+    }
+}
+
+impl<T> CDrop for CArrayView<T> {
+    fn do_drop(&mut self) -> Result<(), Error> {
+        // Borrowed view: never `Box::from_raw` memory we do not own.
+        Ok(())
+    }
+}
+
+/// A utility type to represent a fixed-size array embedded inline in a C struct.
+/// Unlike [`CArray`], which stores a pointer and a size and is laid out as a
+/// `Vec`-like pair, `CFixedArray` stores its `N` elements contiguously inside the
+/// struct, mirroring a C field declared as `T field[N]`.
+/// Note that the parametrized type should have a C-compatible representation.
+///
+/// # Example
+///
+/// ```
+/// use ffi_convert::{CReprOf, AsRust, CFixedArray};
+///
+/// let coeffs: [f32; 4] = [0.0, 0.25, 0.5, 1.0];
+/// let c_coeffs = CFixedArray::<f32, 4>::c_repr_of(coeffs).expect("could not convert !");
+/// let coeffs_converted = c_coeffs.as_rust().expect("could not convert !");
+/// assert_eq!(coeffs, coeffs_converted);
+/// ```
+#[repr(C)]
+#[derive(Debug)]
+pub struct CFixedArray<T, const N: usize> {
+    /// The `N` elements of the array, stored inline.
+    pub values: [T; N],
+}
+
+impl<U: AsRust<V>, V, const N: usize> AsRust<[V; N]> for CFixedArray<U, N> {
+    fn as_rust(&self) -> Result<[V; N], Error> {
+        let mut vec = Vec::with_capacity(N);
+        for value in self.values.iter() {
+            vec.push(value.as_rust()?);
+        }
+        let values: [V; N] = vec
+            .try_into()
+            .map_err(|_: Vec<V>| ())
+            .expect("the vector was built with exactly N elements, so the conversion never fails");
+        Ok(values)
+    }
+}
+
+impl<U: CReprOf<V> + CDrop, V, const N: usize> CReprOf<[V; N]> for CFixedArray<U, N> {
+    fn c_repr_of(input: [V; N]) -> Result<Self, Error> {
+        let mut vec = Vec::with_capacity(N);
+        for item in input {
+            vec.push(U::c_repr_of(item)?);
+        }
+        let values: [U; N] = vec
+            .try_into()
+            .map_err(|_: Vec<U>| ())
+            .expect("the vector was built with exactly N elements, so the conversion never fails");
+        Ok(Self { values })
+    }
+}
+
+impl<T, const N: usize> CDrop for CFixedArray<T, N> {
+    fn do_drop(&mut self) -> Result<(), Error> {
+        // The elements are stored inline, so the `[T; N]` field's own drop glue
+        // runs each element's `Drop` (hence `do_drop`) exactly once. Freeing them
+        // here as well would double-free, so — like `CRange` — this is a no-op.
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> Drop for CFixedArray<T, N> {
+    fn drop(&mut self) {
+        let _ = self.do_drop();
+    }
+}
+
+/// A utility type to represent an OS string across the FFI boundary while
+/// preserving the platform-native encoding.
+///
+/// Unlike converting through a [`String`], this type does **not** force UTF-8
+/// validation: on Unix the raw bytes of the [`OsString`] are kept verbatim
+/// (through [`OsStringExt`](std::os::unix::ffi::OsStringExt)), and on Windows the
+/// UTF-16 code units are preserved. This avoids silent data loss on paths that
+/// are not valid UTF-8.
+///
+/// # Example
+///
+/// ```
+/// use ffi_convert::{CReprOf, AsRust, COsString};
+/// use std::ffi::OsString;
+///
+/// let os_string = OsString::from("café");
+/// let c_os_string = COsString::c_repr_of(os_string.clone()).expect("could not convert !");
+/// assert_eq!(os_string, c_os_string.as_rust().expect("could not convert !"));
+/// ```
+#[repr(C)]
+#[derive(Debug)]
+pub struct COsString {
+    /// Pointer to the native-encoded bytes of the string.
+    pub data: *const u8,
+    /// Number of bytes pointed to by `data`.
+    // Note: we can't use `libc::size_t` because it's not supported by JNA
+    pub size: libc::c_int,
+}
+
+unsafe impl Sync for COsString {}
+
+impl COsString {
+    fn from_bytes(bytes: Vec<u8>) -> Self {
+        let boxed = bytes.into_boxed_slice();
+        Self {
+            size: boxed.len() as libc::c_int,
+            data: Box::into_raw(boxed) as *const u8,
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        if self.size <= 0 {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(self.data, self.size as usize) }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn os_string_to_native_bytes(os_string: OsString) -> Vec<u8> {
+    use std::os::unix::ffi::OsStringExt;
+    os_string.into_vec()
+}
+
+#[cfg(unix)]
+fn native_bytes_to_os_string(bytes: &[u8]) -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+    OsString::from_vec(bytes.to_vec())
+}
+
+#[cfg(windows)]
+fn os_string_to_native_bytes(os_string: OsString) -> Vec<u8> {
+    use std::os::windows::ffi::OsStrExt;
+    // Store the UTF-16 code units as little-endian byte pairs.
+    os_string
+        .encode_wide()
+        .flat_map(|unit| unit.to_le_bytes().to_vec())
+        .collect()
+}
+
+#[cfg(windows)]
+fn native_bytes_to_os_string(bytes: &[u8]) -> OsString {
+    use std::os::windows::ffi::OsStringExt;
+    let wide: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    OsString::from_wide(&wide)
+}
+
+impl CReprOf<OsString> for COsString {
+    fn c_repr_of(input: OsString) -> Result<Self, Error> {
+        Ok(Self::from_bytes(os_string_to_native_bytes(input)))
+    }
+}
+
+impl AsRust<OsString> for COsString {
+    fn as_rust(&self) -> Result<OsString, Error> {
+        Ok(native_bytes_to_os_string(self.as_bytes()))
+    }
+}
+
+impl CDrop for COsString {
+    fn do_drop(&mut self) -> Result<(), Error> {
+        if !self.data.is_null() {
+            let _ = unsafe {
+                Box::from_raw(std::slice::from_raw_parts_mut(
+                    self.data as *mut u8,
+                    self.size as usize,
+                ))
+            };
+        }
+        Ok(())
+    }
+}
+
+impl Drop for COsString {
+    fn drop(&mut self) {
+        let _ = self.do_drop();
+    }
+}
+
+/// A utility type to represent a filesystem path across the FFI boundary while
+/// preserving the platform-native encoding.
+///
+/// This is the [`PathBuf`] counterpart of [`COsString`]: it reuses the same
+/// native-encoding storage so that paths which are not valid UTF-8 survive the
+/// round-trip unchanged.
+///
+/// # Example
+///
+/// ```
+/// use ffi_convert::{CReprOf, AsRust, CPath};
+/// use std::path::PathBuf;
+///
+/// let path = PathBuf::from("/etc/hostname");
+/// let c_path = CPath::c_repr_of(path.clone()).expect("could not convert !");
+/// assert_eq!(path, c_path.as_rust().expect("could not convert !"));
+/// ```
+#[repr(C)]
+#[derive(Debug)]
+pub struct CPath {
+    /// Pointer to the native-encoded bytes of the path.
+    pub data: *const u8,
+    /// Number of bytes pointed to by `data`.
+    pub size: libc::c_int,
+}
+
+unsafe impl Sync for CPath {}
+
+impl CReprOf<PathBuf> for CPath {
+    fn c_repr_of(input: PathBuf) -> Result<Self, Error> {
+        // Keep the intermediate `COsString` from running its `Drop` (which would
+        // free the buffer we are about to take ownership of) before we copy the
+        // pointer out of it.
+        let c = std::mem::ManuallyDrop::new(COsString::c_repr_of(input.into_os_string())?);
+        Ok(Self {
+            data: c.data,
+            size: c.size,
+        })
+    }
+}
+
+impl AsRust<PathBuf> for CPath {
+    fn as_rust(&self) -> Result<PathBuf, Error> {
+        let bytes = if self.size <= 0 {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(self.data, self.size as usize) }
+        };
+        Ok(PathBuf::from(native_bytes_to_os_string(bytes)))
+    }
+}
+
+impl CDrop for CPath {
+    fn do_drop(&mut self) -> Result<(), Error> {
+        if !self.data.is_null() {
+            let _ = unsafe {
+                Box::from_raw(std::slice::from_raw_parts_mut(
+                    self.data as *mut u8,
+                    self.size as usize,
+                ))
+            };
+        }
+        Ok(())
+    }
+}
+
+impl Drop for CPath {
+    fn drop(&mut self) {
+        let _ = self.do_drop();
+    }
+}
+
 /// A utility type to represent range.
 /// Note that the parametrized type T should have have `CReprOf` and `AsRust` trait implementated.
 ///